@@ -1,10 +1,36 @@
+// no_std: build with --no-default-features --features core_io
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+extern crate alloc;
+
+use alloc::collections::VecDeque;
+use alloc::rc::{Rc, Weak};
+use alloc::string::String;
+use alloc::vec::Vec;
+use combine::error::{FastResult, StreamError};
+#[cfg(feature = "std")]
 use combine::stream::easy::Errors;
-use combine::stream::{Positioned, Resetable, StreamErrorFor, StreamOnce};
+use combine::stream::{Positioned, RangeStreamOnce, Resetable, StreamErrorFor, StreamOnce};
+use core::cell::{Cell, RefCell};
+use core::fmt;
 use core::num::NonZeroUsize;
-use std::cell::{Cell, RefCell};
-use std::collections::VecDeque;
-use std::io::Read;
-use std::rc::{Rc, Weak};
+use core::ops::Deref;
+use memchr::memchr;
+
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
+
+#[cfg(feature = "std")]
+mod io_backend {
+    pub use std::io::{Error as IoError, ErrorKind, Read, Result as IoResult, SeekFrom};
+}
+
+#[cfg(all(feature = "core_io", not(feature = "std")))]
+mod io_backend {
+    pub use core_io::{Error as IoError, ErrorKind, Read, Result as IoResult, SeekFrom};
+}
+
+use io_backend::{ErrorKind, IoError, IoResult, Read, SeekFrom};
 
 const ITEM_INDEX_SIZE: usize = 13;
 const ITEM_INDEX_MASK: usize = (1 << ITEM_INDEX_SIZE) - 1;
@@ -78,13 +104,105 @@ impl CheckPointSet {
     }
 }
 
+#[derive(Clone)]
+pub enum ElasticRange {
+    Chunk {
+        // Cheap: an Rc clone of a buffered chunk, not a copy.
+        chunk: Rc<[u8; CHUNK_SIZE]>,
+        start: usize,
+        len: usize,
+    },
+    Owned(Rc<[u8]>), // Copied once, for a range that straddled a chunk boundary.
+}
+
+impl ElasticRange {
+    fn empty() -> ElasticRange {
+        ElasticRange::Owned(Rc::from(Vec::new()))
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            ElasticRange::Chunk { chunk, start, len } => &chunk[*start..*start + *len],
+            ElasticRange::Owned(bytes) => bytes,
+        }
+    }
+}
+
+impl Deref for ElasticRange {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl PartialEq for ElasticRange {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for ElasticRange {}
+
+impl fmt::Debug for ElasticRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl fmt::Display for ElasticRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(self.as_slice()))
+    }
+}
+
+#[cfg(feature = "std")]
+pub type ElasticStreamError = combine::stream::easy::Error<u8, ElasticRange>;
+// no_std has no allocation-free way to carry an easy::Error, so it falls back to this instead.
+#[cfg(not(feature = "std"))]
+pub type ElasticStreamError = combine::error::UnexpectedParse;
+
+#[cfg(feature = "std")]
+pub type ElasticParseError = Errors<u8, ElasticRange, u64>;
+#[cfg(not(feature = "std"))]
+pub type ElasticParseError = combine::error::UnexpectedParse;
+
+// `UnexpectedParse`'s `StreamError` impl is generic over every `Item`/`Range`, so calling e.g.
+// `ElasticStreamError::end_of_input()` directly leaves the compiler unable to infer which
+// instantiation is meant. Routing through these helpers with a fully-qualified trait call pins
+// it to the concrete `(u8, ElasticRange)` this stream actually uses.
+fn end_of_input_error() -> ElasticStreamError {
+    <ElasticStreamError as StreamError<u8, ElasticRange>>::end_of_input()
+}
+
+fn unexpected_message_error(message: &'static str) -> ElasticStreamError {
+    <ElasticStreamError as StreamError<u8, ElasticRange>>::unexpected_static_message(message)
+}
+
+// Retryable: unlike end_of_input_error, more data may still arrive via feed().
+fn needs_more_data_error() -> ElasticStreamError {
+    unexpected_message_error("need more data")
+}
+
+// Stands in for the reader type parameter of a push_based stream, which is fed via feed()
+// instead of ever being read from.
+pub struct NoReader(());
+
+impl Read for NoReader {
+    fn read(&mut self, _buf: &mut [u8]) -> IoResult<usize> {
+        unreachable!("a push-based stream never reads from its underlying reader")
+    }
+}
+
 pub struct ElasticBufferedReadStream<R: Read> {
     raw_read: R,
-    buffer: VecDeque<[u8; CHUNK_SIZE]>,
+    buffer: VecDeque<Rc<[u8; CHUNK_SIZE]>>,
     eof: Option<NonZeroUsize>,
     checkpoints: CheckPointSet,
     cursor_pos: usize,
     offset: u64, // The capacity of this parameter limits the size of the stream
+    push_based: bool,
+    write_pos: usize, // Only meaningful when `push_based`: how much of the buffer `feed` has filled in.
 }
 
 impl<R: Read> ElasticBufferedReadStream<R> {
@@ -96,6 +214,8 @@ impl<R: Read> ElasticBufferedReadStream<R> {
             checkpoints: CheckPointSet::new(),
             cursor_pos: 0,
             offset: 0,
+            push_based: false,
+            write_pos: 0,
         }
     }
 
@@ -119,17 +239,290 @@ impl<R: Read> ElasticBufferedReadStream<R> {
         self.cursor_pos -= offset_delta;
         self.offset += offset_delta as u64;
         self.checkpoints.sub_offset(offset_delta);
+        if self.push_based {
+            self.write_pos -= offset_delta;
+        }
     }
 
     pub fn buffer_len(&self) -> usize {
         self.buffer.len()
     }
+
+    fn buffered_len(&self) -> usize {
+        self.buffer.len() * CHUNK_SIZE
+    }
+
+    // Bytes available before end of input, once known; for a push_based stream with no mark_eof
+    // yet, the number of bytes fed so far instead.
+    fn available_len(&self) -> Option<usize> {
+        match self.eof {
+            Some(eof_pos_from_right) => Some(self.buffered_len() - eof_pos_from_right.get()),
+            None if self.push_based => Some(self.write_pos),
+            None => None,
+        }
+    }
+
+    // Reads and buffers one more chunk. Must not be called once EOF has been reached.
+    fn load_next_chunk(&mut self) -> Result<(), StreamErrorFor<Self>> {
+        if self.push_based {
+            return Err(needs_more_data_error());
+        }
+
+        assert!(self.eof.is_none());
+        self.free_useless_chunks();
+        self.buffer.push_back(Rc::new([0; CHUNK_SIZE]));
+
+        let chunk = Rc::get_mut(self.buffer.back_mut().unwrap())
+            .expect("a chunk that was just pushed cannot be shared yet");
+        self.eof =
+            read_exact_or_eof(&mut self.raw_read, chunk).map_err(io_error_to_stream_error)?;
+
+        Ok(())
+    }
+
+    // Buffers chunks until target_pos is covered or end of input is reached.
+    fn ensure_buffered(&mut self, target_pos: usize) -> Result<(), StreamErrorFor<Self>> {
+        while self.eof.is_none() && self.buffered_len() < target_pos {
+            self.load_next_chunk()?;
+        }
+
+        Ok(())
+    }
+
+    // Same push_based-retryable-vs-real-eof distinction as uncons() makes, shared by the
+    // RangeStreamOnce methods below.
+    fn exhausted_error(&self) -> StreamErrorFor<Self> {
+        if self.push_based && self.eof.is_none() {
+            needs_more_data_error()
+        } else {
+            end_of_input_error()
+        }
+    }
+
+    fn resolve_seek_target(&mut self, pos: SeekFrom) -> Result<u64, SeekError> {
+        match pos {
+            SeekFrom::Start(offset) => Ok(offset),
+            SeekFrom::Current(delta) => self
+                .position()
+                .checked_add_signed(delta)
+                .ok_or(SeekError::InvalidOffset),
+            SeekFrom::End(delta) => {
+                self.ensure_buffered(usize::MAX).map_err(SeekError::Read)?;
+                let total_len = self.offset
+                    + self
+                        .available_len()
+                        .expect("eof is known once ensure_buffered(usize::MAX) returns") as u64;
+
+                let target = total_len
+                    .checked_add_signed(delta)
+                    .ok_or(SeekError::InvalidOffset)?;
+
+                Ok(target.min(total_len))
+            }
+        }
+    }
+
+    // Repositions the cursor within the live buffer window. A backward seek past what
+    // free_useless_chunks has already discarded fails with BeforeBufferedWindow; a forward seek
+    // reads on demand and clamps to the end of the stream once EOF is known.
+    pub fn seek(&mut self, pos: SeekFrom) -> Result<u64, SeekError> {
+        let target = self.resolve_seek_target(pos)?;
+
+        if target < self.offset {
+            return Err(SeekError::BeforeBufferedWindow {
+                requested: target,
+                window_start: self.offset,
+            });
+        }
+
+        let local_target = (target - self.offset) as usize;
+
+        // distance() assumes the cursor only ever moves forward relative to a live checkpoint;
+        // a backward seek past one would make it underflow.
+        if let Some(checkpoint_min) = self.checkpoints.min() {
+            if local_target < checkpoint_min {
+                return Err(SeekError::BeforeLiveCheckpoint {
+                    requested: target,
+                    checkpoint: self.offset + checkpoint_min as u64,
+                });
+            }
+        }
+
+        self.ensure_buffered(local_target).map_err(SeekError::Read)?;
+
+        self.cursor_pos = match self.available_len() {
+            Some(available) => local_target.min(available),
+            None => local_target,
+        };
+
+        Ok(self.position())
+    }
+
+    // Scans forward from the cursor for delim and returns the position just past it, or the
+    // position of end of input if delim never appears. Leaves the cursor untouched, so a
+    // checkpoint further back doesn't get its chunks freed by the scan.
+    fn scan_to_delim(&mut self, delim: u8) -> Result<usize, StreamErrorFor<Self>> {
+        let mut pos = self.cursor_pos;
+
+        loop {
+            let chunk_idx = pos >> ITEM_INDEX_SIZE;
+            let item_idx = pos & ITEM_INDEX_MASK;
+
+            if chunk_idx == self.buffer.len() {
+                if self.eof.is_some() {
+                    return Ok(pos);
+                }
+                self.load_next_chunk()?;
+            }
+
+            let chunk_upper = match self.available_len() {
+                Some(available) => available.min((chunk_idx + 1) * CHUNK_SIZE) - chunk_idx * CHUNK_SIZE,
+                None => CHUNK_SIZE,
+            };
+
+            if item_idx >= chunk_upper {
+                if self.push_based && self.eof.is_none() {
+                    return Err(needs_more_data_error());
+                }
+                return Ok(pos); // Reached end of input partway through this chunk.
+            }
+
+            match memchr(delim, &self.buffer[chunk_idx][item_idx..chunk_upper]) {
+                Some(rel) => return Ok(pos + rel + 1),
+                None => pos = chunk_idx * CHUNK_SIZE + chunk_upper,
+            }
+        }
+    }
+
+    // Returns the bytes up to and including the next occurrence of delim, or up to end of input
+    // if delim is never found.
+    pub fn uncons_until(&mut self, delim: u8) -> Result<ElasticRange, StreamErrorFor<Self>> {
+        let start_pos = self.cursor_pos;
+        let end_pos = self.scan_to_delim(delim)?;
+        self.cursor_pos = start_pos;
+
+        self.uncons_range(end_pos - start_pos)
+    }
+
+    // Like uncons_until, but discards the scanned bytes instead of returning them.
+    pub fn skip_until(&mut self, delim: u8) -> Result<(), StreamErrorFor<Self>> {
+        self.cursor_pos = self.scan_to_delim(delim)?;
+
+        Ok(())
+    }
+}
+
+impl ElasticBufferedReadStream<NoReader> {
+    // A stream fed by feed() instead of reading from a Read source, for data that arrives
+    // incrementally (e.g. over a socket).
+    pub fn push_based() -> Self {
+        Self {
+            raw_read: NoReader(()),
+            buffer: VecDeque::new(),
+            eof: None,
+            checkpoints: CheckPointSet::new(),
+            cursor_pos: 0,
+            offset: 0,
+            push_based: true,
+            write_pos: 0,
+        }
+    }
+
+    // Bytes of the last buffered chunk feed() has written, or CHUNK_SIZE if there's no room left
+    // (including when the buffer is empty).
+    fn push_tail_fill(&self) -> usize {
+        match self.buffer.len() {
+            0 => CHUNK_SIZE,
+            chunks => self.write_pos - (chunks - 1) * CHUNK_SIZE,
+        }
+    }
+
+    // Appends data to the stream. Must not be called after mark_eof.
+    pub fn feed(&mut self, mut data: &[u8]) {
+        debug_assert!(self.eof.is_none(), "cannot feed data after mark_eof");
+
+        while !data.is_empty() {
+            if self.push_tail_fill() == CHUNK_SIZE {
+                self.free_useless_chunks();
+                self.buffer.push_back(Rc::new([0; CHUNK_SIZE]));
+            }
+
+            let fill = self.push_tail_fill();
+            // A caller may still be holding an `ElasticRange::Chunk` borrowed from this same
+            // tail chunk (e.g. via `uncons_range`) while more data is fed into it, so the `Rc`
+            // can already be shared here; `make_mut` copies in that case instead of panicking,
+            // leaving the borrowed range pointing at the bytes it saw.
+            let chunk = Rc::make_mut(self.buffer.back_mut().unwrap());
+            let take = (CHUNK_SIZE - fill).min(data.len());
+            chunk[fill..fill + take].copy_from_slice(&data[..take]);
+
+            self.write_pos += take;
+            data = &data[take..];
+        }
+    }
+
+    // Signals that no more data will ever be fed: uncons() now reports end_of_input_error rather
+    // than needs_more_data_error once the fed data is exhausted.
+    pub fn mark_eof(&mut self) {
+        if self.push_tail_fill() == CHUNK_SIZE {
+            self.free_useless_chunks();
+            self.buffer.push_back(Rc::new([0; CHUNK_SIZE]));
+        }
+
+        self.eof = NonZeroUsize::new(CHUNK_SIZE - self.push_tail_fill());
+    }
+}
+
+#[derive(Debug)]
+pub enum SeekError {
+    BeforeBufferedWindow { requested: u64, window_start: u64 }, // bytes already freed
+    BeforeLiveCheckpoint { requested: u64, checkpoint: u64 }, // would invalidate distance()
+    InvalidOffset, // a Current/End offset under- or overflowed u64
+    Read(ElasticStreamError),
+}
+
+impl fmt::Display for SeekError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeekError::BeforeBufferedWindow {
+                requested,
+                window_start,
+            } => write!(
+                f,
+                "cannot seek to {} because it is before the start of the buffered window ({})",
+                requested, window_start
+            ),
+            SeekError::BeforeLiveCheckpoint {
+                requested,
+                checkpoint,
+            } => write!(
+                f,
+                "cannot seek to {} because it is before a live checkpoint ({})",
+                requested, checkpoint
+            ),
+            SeekError::InvalidOffset => write!(f, "seek offset underflowed or overflowed"),
+            SeekError::Read(err) => write!(f, "failed to read while seeking: {:?}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl StdError for SeekError {}
+
+#[cfg(feature = "std")]
+fn io_error_to_stream_error(err: IoError) -> ElasticStreamError {
+    err.into()
+}
+
+#[cfg(not(feature = "std"))]
+fn io_error_to_stream_error(_err: IoError) -> ElasticStreamError {
+    unexpected_message_error("i/o error")
 }
 
 fn read_exact_or_eof<R: Read>(
     reader: &mut R,
     mut chunk: &mut [u8],
-) -> std::io::Result<Option<NonZeroUsize>> {
+) -> IoResult<Option<NonZeroUsize>> {
     while !chunk.is_empty() {
         match reader.read(chunk) {
             Ok(0) => break,
@@ -137,7 +530,7 @@ fn read_exact_or_eof<R: Read>(
                 let tmp = chunk;
                 chunk = &mut tmp[n..];
             }
-            Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
             Err(e) => return Err(e),
         }
     }
@@ -147,26 +540,19 @@ fn read_exact_or_eof<R: Read>(
 
 impl<R: Read> StreamOnce for ElasticBufferedReadStream<R> {
     type Item = u8;
-    type Range = u8; // TODO: Change it when we implement RangeStream
+    type Range = ElasticRange;
     type Position = u64;
-    type Error = Errors<u8, u8, u64>;
+    type Error = ElasticParseError;
 
     fn uncons(&mut self) -> Result<u8, StreamErrorFor<Self>> {
         assert!(self.chunk_index() <= self.buffer.len());
 
         if self.chunk_index() == self.buffer.len() {
-            assert!(self.eof.is_none());
-            self.free_useless_chunks();
-            self.buffer.push_back([0; CHUNK_SIZE]);
-            self.eof = read_exact_or_eof(&mut self.raw_read, self.buffer.back_mut().unwrap())?;
+            self.load_next_chunk()?;
         }
 
-        if self.chunk_index() == self.buffer.len() - 1 {
-            if let Some(eof_pos_from_right) = self.eof {
-                if self.item_index() >= CHUNK_SIZE - eof_pos_from_right.get() {
-                    return Err(StreamErrorFor::<Self>::end_of_input());
-                }
-            }
+        if self.available_len().is_some_and(|available| self.cursor_pos >= available) {
+            return Err(self.exhausted_error());
         }
 
         let chunk = self.buffer.get(self.chunk_index()).unwrap(); // We can unwrap because self.buffer.len() > chunk_index
@@ -177,6 +563,114 @@ impl<R: Read> StreamOnce for ElasticBufferedReadStream<R> {
     }
 }
 
+impl<R: Read> RangeStreamOnce for ElasticBufferedReadStream<R> {
+    fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
+        let target_pos = self.cursor_pos + size;
+        self.ensure_buffered(target_pos)?;
+
+        if self.available_len().is_some_and(|available| target_pos > available) {
+            return Err(self.exhausted_error());
+        }
+
+        if size == 0 {
+            return Ok(ElasticRange::empty());
+        }
+
+        let start_chunk = self.chunk_index();
+        let start_item = self.item_index();
+        let end_chunk = target_pos >> ITEM_INDEX_SIZE;
+        let end_item = target_pos & ITEM_INDEX_MASK;
+
+        let range = if end_chunk == start_chunk || (end_chunk == start_chunk + 1 && end_item == 0)
+        {
+            let len = if end_chunk == start_chunk {
+                end_item - start_item
+            } else {
+                CHUNK_SIZE - start_item
+            };
+
+            ElasticRange::Chunk {
+                chunk: self.buffer[start_chunk].clone(),
+                start: start_item,
+                len,
+            }
+        } else {
+            let mut scratch = Vec::with_capacity(size);
+            let mut remaining = size;
+            let mut chunk_idx = start_chunk;
+            let mut item_idx = start_item;
+
+            while remaining > 0 {
+                let chunk = &self.buffer[chunk_idx];
+                let take = (CHUNK_SIZE - item_idx).min(remaining);
+                scratch.extend_from_slice(&chunk[item_idx..item_idx + take]);
+
+                remaining -= take;
+                chunk_idx += 1;
+                item_idx = 0;
+            }
+
+            ElasticRange::Owned(Rc::from(scratch))
+        };
+
+        self.cursor_pos = target_pos;
+
+        Ok(range)
+    }
+
+    fn uncons_while<F>(&mut self, mut f: F) -> Result<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Item) -> bool,
+    {
+        let start_pos = self.cursor_pos;
+        let mut pos = start_pos;
+
+        loop {
+            let chunk_idx = pos >> ITEM_INDEX_SIZE;
+            let item_idx = pos & ITEM_INDEX_MASK;
+
+            if chunk_idx == self.buffer.len() {
+                if self.eof.is_some() {
+                    break;
+                }
+                // On error self.cursor_pos is still start_pos (it's only written below, on the
+                // way out), so a failed load here doesn't strand the cursor past unread bytes.
+                self.load_next_chunk()?;
+            }
+
+            if self.available_len().is_some_and(|available| pos >= available) {
+                break;
+            }
+
+            let item = self.buffer[chunk_idx][item_idx];
+            if !f(item) {
+                break;
+            }
+
+            pos += 1;
+        }
+
+        self.cursor_pos = start_pos;
+
+        self.uncons_range(pos - start_pos)
+    }
+
+    fn uncons_while1<F>(&mut self, f: F) -> FastResult<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Item) -> bool,
+    {
+        match self.uncons_while(f) {
+            Ok(range) if range.is_empty() => FastResult::EmptyErr(self.exhausted_error().into()),
+            Ok(range) => FastResult::ConsumedOk(range),
+            Err(err) => FastResult::EmptyErr(err.into()),
+        }
+    }
+
+    fn distance(&self, end: &Self::Checkpoint) -> usize {
+        self.cursor_pos - end.inner()
+    }
+}
+
 impl<R: Read> Positioned for ElasticBufferedReadStream<R> {
     fn position(&self) -> Self::Position {
         self.offset + self.cursor_pos as u64
@@ -354,4 +848,259 @@ mod tests {
 
         assert_eq!(stream.buffer_len(), 1);
     }
+
+    #[test]
+    fn it_uncons_range_within_a_single_chunk() {
+        let fake_read = &b"This is the text !"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        assert_eq!(&*stream.uncons_range(4).unwrap(), b"This");
+        assert_eq!(&*stream.uncons_range(4).unwrap(), b" is ");
+    }
+
+    #[test]
+    fn it_uncons_range_across_chunk_boundaries() {
+        let mut fake_read = String::with_capacity(CHUNK_SIZE * 2);
+        fake_read += &"a".repeat(CHUNK_SIZE - 2);
+        fake_read += "bcdefgh";
+
+        let mut stream = ElasticBufferedReadStream::new(fake_read.as_bytes());
+        assert!(stream.uncons_range(CHUNK_SIZE - 2).is_ok());
+
+        let spanning = stream.uncons_range(5).unwrap();
+        assert_eq!(&*spanning, b"bcdef");
+    }
+
+    #[test]
+    fn it_uncons_range_fails_past_eof() {
+        let fake_read = &b"short"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        assert_eq!(
+            stream.uncons_range(10),
+            Err(StreamErrorFor::<ElasticBufferedReadStream<&[u8]>>::end_of_input())
+        );
+    }
+
+    #[test]
+    fn it_uncons_while_stops_at_predicate() {
+        let fake_read = &b"aaaabcdef"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        let range = stream.uncons_while(|item| item == b'a').unwrap();
+        assert_eq!(&*range, b"aaaa");
+        assert_eq!(stream.uncons(), Ok(b'b'));
+    }
+
+    #[test]
+    fn it_computes_distance_from_checkpoint() {
+        let fake_read = &b"This is the text !"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        let cp = stream.checkpoint();
+        for _ in 0..7 {
+            assert!(stream.uncons().is_ok());
+        }
+
+        assert_eq!(stream.distance(&cp), 7);
+    }
+
+    #[test]
+    fn it_seeks_from_start_and_current() {
+        let fake_read = &b"This is the text !"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        assert_eq!(stream.seek(SeekFrom::Start(5)).unwrap(), 5);
+        assert_eq!(stream.uncons(), Ok(b'i'));
+
+        assert_eq!(stream.seek(SeekFrom::Current(-2)).unwrap(), 4);
+        assert_eq!(stream.uncons(), Ok(b' '));
+    }
+
+    #[test]
+    fn it_seeks_forward_reading_chunks_on_demand() {
+        let mut fake_read = String::with_capacity(CHUNK_SIZE * 2);
+        fake_read += &"a".repeat(CHUNK_SIZE);
+        fake_read += "bcdef";
+
+        let mut stream = ElasticBufferedReadStream::new(fake_read.as_bytes());
+        assert_eq!(stream.buffer_len(), 0);
+
+        assert_eq!(
+            stream.seek(SeekFrom::Start((CHUNK_SIZE + 2) as u64)).unwrap(),
+            (CHUNK_SIZE + 2) as u64
+        );
+        assert_eq!(stream.uncons(), Ok(b'd'));
+    }
+
+    #[test]
+    fn it_seeks_from_end_and_clamps_past_eof() {
+        let fake_read = &b"This is the text !"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        assert_eq!(stream.seek(SeekFrom::End(-1)).unwrap(), 17);
+        assert_eq!(stream.uncons(), Ok(b'!'));
+
+        assert_eq!(stream.seek(SeekFrom::End(10)).unwrap(), 18);
+        assert_eq!(
+            stream.uncons(),
+            Err(StreamErrorFor::<ElasticBufferedReadStream<&[u8]>>::end_of_input())
+        );
+    }
+
+    #[test]
+    fn it_uncons_until_finds_the_delimiter() {
+        let fake_read = &b"This is the text !"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        let segment = stream.uncons_until(b' ').unwrap();
+        assert_eq!(&*segment, b"This ");
+        assert_eq!(stream.uncons(), Ok(b'i'));
+    }
+
+    #[test]
+    fn it_uncons_until_scans_across_chunk_boundaries() {
+        let mut fake_read = String::with_capacity(CHUNK_SIZE * 2);
+        fake_read += &"a".repeat(CHUNK_SIZE + 2);
+        fake_read += "b;cdef";
+
+        let mut stream = ElasticBufferedReadStream::new(fake_read.as_bytes());
+
+        let segment = stream.uncons_until(b';').unwrap();
+        assert_eq!(segment.len(), CHUNK_SIZE + 4);
+        assert_eq!(stream.uncons(), Ok(b'c'));
+    }
+
+    #[test]
+    fn it_uncons_until_returns_the_remainder_at_eof() {
+        let fake_read = &b"no delimiter here"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        let segment = stream.uncons_until(b';').unwrap();
+        assert_eq!(&*segment, b"no delimiter here");
+        assert_eq!(
+            stream.uncons(),
+            Err(StreamErrorFor::<ElasticBufferedReadStream<&[u8]>>::end_of_input())
+        );
+    }
+
+    #[test]
+    fn it_skip_until_discards_the_scanned_bytes() {
+        let fake_read = &b"This is the text !"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        assert!(stream.skip_until(b' ').is_ok());
+        assert_eq!(stream.uncons(), Ok(b'i'));
+    }
+
+    #[test]
+    fn it_unconses_push_based_data_as_it_is_fed() {
+        let mut stream = ElasticBufferedReadStream::push_based();
+
+        stream.feed(b"ab");
+        assert_eq!(stream.uncons(), Ok(b'a'));
+        assert_eq!(stream.uncons(), Ok(b'b'));
+        assert_eq!(stream.uncons(), Err(needs_more_data_error()));
+
+        stream.feed(b"c");
+        assert_eq!(stream.uncons(), Ok(b'c'));
+
+        stream.mark_eof();
+        assert_eq!(
+            stream.uncons(),
+            Err(StreamErrorFor::<ElasticBufferedReadStream<NoReader>>::end_of_input())
+        );
+    }
+
+    #[test]
+    fn it_feeds_push_based_data_across_chunk_boundaries() {
+        let mut stream = ElasticBufferedReadStream::push_based();
+
+        stream.feed(&"a".repeat(CHUNK_SIZE - 1).into_bytes());
+        stream.feed(b"bc");
+
+        for _ in 0..CHUNK_SIZE - 1 {
+            assert_eq!(stream.uncons(), Ok(b'a'));
+        }
+        assert_eq!(stream.uncons(), Ok(b'b'));
+        assert_eq!(stream.uncons(), Ok(b'c'));
+        assert_eq!(stream.uncons(), Err(needs_more_data_error()));
+    }
+
+    #[test]
+    fn it_leaves_the_cursor_put_when_uncons_while_needs_more_data() {
+        let mut stream = ElasticBufferedReadStream::push_based();
+        stream.feed(&"a".repeat(CHUNK_SIZE).into_bytes());
+
+        assert_eq!(
+            stream.uncons_while(|item| item == b'a'),
+            Err(needs_more_data_error())
+        );
+
+        stream.feed(b"bbb");
+        stream.mark_eof();
+
+        let range = stream.uncons_while(|item| item == b'a').unwrap();
+        assert_eq!(range.len(), CHUNK_SIZE);
+        assert_eq!(&*stream.uncons_until(b'\0').unwrap(), b"bbb");
+    }
+
+    #[test]
+    fn it_resets_push_based_streams_on_checkpoint() {
+        let mut stream = ElasticBufferedReadStream::push_based();
+        stream.feed(b"abcdef");
+
+        let cp = stream.checkpoint();
+        assert_eq!(stream.uncons(), Ok(b'a'));
+        assert_eq!(stream.uncons(), Ok(b'b'));
+
+        stream.reset(cp);
+        assert_eq!(stream.uncons(), Ok(b'a'));
+    }
+
+    #[test]
+    fn it_fails_to_seek_before_the_buffered_window() {
+        let mut fake_read = String::with_capacity(CHUNK_SIZE * 2);
+        fake_read += &"a".repeat(CHUNK_SIZE);
+        fake_read += "bcdef";
+
+        let mut stream = ElasticBufferedReadStream::new(fake_read.as_bytes());
+
+        // With no live checkpoints, reading past the first chunk boundary frees that chunk.
+        for _ in 0..CHUNK_SIZE + 1 {
+            assert!(stream.uncons().is_ok());
+        }
+        assert_eq!(stream.position(), (CHUNK_SIZE + 1) as u64);
+
+        match stream.seek(SeekFrom::Start(0)) {
+            Err(SeekError::BeforeBufferedWindow {
+                requested: 0,
+                window_start,
+            }) => assert_eq!(window_start, CHUNK_SIZE as u64),
+            other => panic!("expected BeforeBufferedWindow, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_fails_to_seek_before_a_live_checkpoint() {
+        let fake_read = &b"abcdef"[..];
+        let mut stream = ElasticBufferedReadStream::new(fake_read);
+
+        assert_eq!(stream.uncons(), Ok(b'a'));
+        assert_eq!(stream.uncons(), Ok(b'b'));
+        let cp = stream.checkpoint();
+        assert_eq!(stream.uncons(), Ok(b'c'));
+
+        match stream.seek(SeekFrom::Start(0)) {
+            Err(SeekError::BeforeLiveCheckpoint {
+                requested: 0,
+                checkpoint,
+            }) => assert_eq!(checkpoint, 2),
+            other => panic!("expected BeforeLiveCheckpoint, got {:?}", other),
+        }
+
+        // Seeking to the checkpoint itself, or anywhere after it, is still fine.
+        assert_eq!(stream.seek(SeekFrom::Start(2)).unwrap(), 2);
+        assert_eq!(stream.distance(&cp), 0);
+    }
 }